@@ -0,0 +1,55 @@
+#!/bin/bash
+# Rebuilds runtime/runtime.o from the Runa sources in runtime/src.
+# Each module is compiled by runac (build --emit=obj) and the objects are
+# merged with ld -r into the single runtime.o programs link against.
+# The result is checked against runtime_symbols.txt before it replaces
+# runtime.o.
+#
+# Usage: runtime/build_runtime.sh [path/to/runac]
+#        (default: $RUNAC, else build/runac)
+
+set -e
+
+SCRIPT_DIR="$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)"
+PROJECT_DIR="$(dirname "$SCRIPT_DIR")"
+SOURCE_DIR="$SCRIPT_DIR/src"
+MANIFEST="$SCRIPT_DIR/runtime_symbols.txt"
+OUTPUT="$SCRIPT_DIR/runtime.o"
+COMPILER="${1:-${RUNAC:-$PROJECT_DIR/build/runac}}"
+# The runtime's own modules are compiled against the prelude next to them.
+export RUNA_PRELUDE="${RUNA_PRELUDE:-$SCRIPT_DIR/prelude.runa}"
+
+if [ ! -x "$COMPILER" ]; then
+    echo "build_runtime.sh: compiler not found: $COMPILER" >&2
+    exit 1
+fi
+
+WORK_DIR="$(mktemp -d)"
+trap 'rm -rf "$WORK_DIR"' EXIT
+
+"$COMPILER" build --emit=obj "$SOURCE_DIR"/runtime_*.runa -o "$WORK_DIR/obj" > "$WORK_DIR/build.log" 2>&1 || {
+    cat "$WORK_DIR/build.log" >&2
+    exit 1
+}
+ld -r -o "$WORK_DIR/runtime.o" "$WORK_DIR"/obj/*.o
+
+# The runtime is linked on its own; nothing may be left for libc to supply.
+# The only outside symbols are the bounds of the runa_functions section
+# (runtime_stack.runa), which the linker defines.
+UNDEFINED="$(nm -u "$WORK_DIR/runtime.o" | grep -v ' __st\(art\|op\)_runa_functions$' || true)"
+if [ -n "$UNDEFINED" ]; then
+    echo "build_runtime.sh: runtime.o has undefined symbols:" >&2
+    echo "$UNDEFINED" >&2
+    exit 1
+fi
+
+nm -g --defined-only "$WORK_DIR/runtime.o" | awk '{print $3}' | grep -v '__module_init$' | sort > "$WORK_DIR/defined.txt"
+grep -v '^#' "$MANIFEST" | awk 'NF {print $1}' | sort > "$WORK_DIR/manifest.txt"
+if ! diff -u "$WORK_DIR/manifest.txt" "$WORK_DIR/defined.txt" > "$WORK_DIR/symbols.diff"; then
+    echo "build_runtime.sh: runtime.o does not match runtime_symbols.txt (- manifest, + built):" >&2
+    tail -n +3 "$WORK_DIR/symbols.diff" | grep '^[-+]' >&2
+    exit 1
+fi
+
+cp "$WORK_DIR/runtime.o" "$OUTPUT"
+echo "Built $OUTPUT ($(wc -l < "$WORK_DIR/defined.txt") symbols)"
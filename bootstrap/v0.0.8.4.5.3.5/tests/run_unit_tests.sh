@@ -34,7 +34,30 @@ echo "Test Results - $(date)" > "$RESULTS_FILE"
 echo "========================================" >> "$RESULTS_FILE"
 
 # Helper files that are not standalone tests
-HELPER_FILES=("test_imports_helper.runa")
+HELPER_FILES=("test_imports_helper.runa" "test_emit_exe_helper.runa" "test_names_first_helper.runa" "test_names_second_helper.runa" "test_names_conflict_helper.runa")
+
+# Multi-module tests built in one step with --emit=exe, which compiles and
+# links every module the test imports
+EXE_FILES=("test_closures.runa" "test_emit_exe_imports.runa" "test_private_names.runa")
+
+# Tests built with --emit=obj, so runac's own object writer assembles them
+OBJ_FILES=("test_lambda.runa")
+
+# Tests that must fail to compile. Each `Note: expect: <text>` line in the
+# file is text the compiler's output has to contain.
+ERROR_FILES=("test_error_interpolation_unknown.runa" "test_error_interpolation_process.runa" "test_error_builtin_arity.runa" "test_error_string_compare.runa" "test_error_tail_call.runa" "test_error_duplicate_global.runa" "test_error_duplicate_type.runa")
+
+# Tests whose program must stop with a runtime error. The program has to
+# exit with the `Note: expect exit: <status>` status, and each
+# `Note: expect: <text>` line is text its output has to contain.
+TRAP_FILES=("test_overflow_trap.runa" "test_stack_overflow_report.runa")
+
+# Any test may pass extra compiler options on a `Note: flags: <options>` line,
+# and each `Note: expect warning: <text>` line is text its compile log has to
+# contain. For a test compiled to .s, each `Note: expect asm: <text>` line is
+# text the assembly has to contain, and with `Note: compare flags: <options>`
+# it is built a second time with those options and both programs have to
+# succeed and print the same output.
 
 # Function to check if file is a helper
 is_helper() {
@@ -47,51 +70,257 @@ is_helper() {
     return 1
 }
 
-# Function to run a single test
-run_test() {
+# Function to check if file is built with --emit=exe
+is_exe_test() {
+    local filename="$1"
+    for exe_file in "${EXE_FILES[@]}"; do
+        if [[ "$filename" == "$exe_file" ]]; then
+            return 0
+        fi
+    done
+    return 1
+}
+
+# Function to check if file is built with --emit=obj
+is_obj_test() {
+    local filename="$1"
+    for obj_file in "${OBJ_FILES[@]}"; do
+        if [[ "$filename" == "$obj_file" ]]; then
+            return 0
+        fi
+    done
+    return 1
+}
+
+# Function to check if file must fail to compile
+is_error_test() {
+    local filename="$1"
+    for error_file in "${ERROR_FILES[@]}"; do
+        if [[ "$filename" == "$error_file" ]]; then
+            return 0
+        fi
+    done
+    return 1
+}
+
+# Function to check if file must stop with a runtime error
+is_trap_test() {
+    local filename="$1"
+    for trap_file in "${TRAP_FILES[@]}"; do
+        if [[ "$filename" == "$trap_file" ]]; then
+            return 0
+        fi
+    done
+    return 1
+}
+
+# Function to run a built test that must stop with the expected status and output
+run_trap_test() {
     local test_file="$1"
     local test_name=$(basename "$test_file" .runa)
     local test_output_dir="$OUTPUT_DIR/$test_name"
+    local expected_exit=$(sed -n 's/^Note: expect exit: //p' "$test_file")
 
-    TOTAL=$((TOTAL + 1))
-
-    # Create test-specific output directory
-    mkdir -p "$test_output_dir"
+    "$test_output_dir/${test_name}" > "$test_output_dir/output.txt" 2>&1
+    local exit_code=$?
+    if [[ "$exit_code" != "$expected_exit" ]]; then
+        echo -e "${RED}FAILED${NC} (exit code: $exit_code, expected $expected_exit)"
+        echo "FAILED: $test_name (exit code: $exit_code, expected $expected_exit)" >> "$RESULTS_FILE"
+        cat "$test_output_dir/output.txt" >> "$RESULTS_FILE"
+        echo "" >> "$RESULTS_FILE"
+        FAILED=$((FAILED + 1))
+        return 1
+    fi
+    while IFS= read -r expected; do
+        if ! grep -qF -- "$expected" "$test_output_dir/output.txt"; then
+            echo -e "${RED}FAILED${NC} (missing expected output)"
+            echo "FAILED: $test_name (missing: $expected)" >> "$RESULTS_FILE"
+            cat "$test_output_dir/output.txt" >> "$RESULTS_FILE"
+            echo "" >> "$RESULTS_FILE"
+            FAILED=$((FAILED + 1))
+            return 1
+        fi
+    done < <(sed -n 's/^Note: expect: //p' "$test_file")
+    echo -e "${GREEN}PASSED${NC}"
+    echo "PASSED: $test_name" >> "$RESULTS_FILE"
+    echo "Output:" >> "$RESULTS_FILE"
+    cat "$test_output_dir/output.txt" >> "$RESULTS_FILE"
+    echo "" >> "$RESULTS_FILE"
+    PASSED=$((PASSED + 1))
+    return 0
+}
 
-    echo -n "Testing $test_name... "
+# Function to run a built test and the same test built with other options
+run_compare_test() {
+    local test_file="$1"
+    local compare_flags="$2"
+    local test_name=$(basename "$test_file" .runa)
+    local test_output_dir="$OUTPUT_DIR/$test_name"
+    local compare_name="${test_name}_compare"
 
-    # Step 1: Compile .runa to .s
-    if ! "$COMPILER" "$test_file" "$test_output_dir/${test_name}.s" > "$test_output_dir/compile.log" 2>&1; then
-        echo -e "${RED}FAILED${NC} (compilation)"
-        echo "FAILED: $test_name (compilation error)" >> "$RESULTS_FILE"
-        cat "$test_output_dir/compile.log" >> "$RESULTS_FILE"
+    if ! "$COMPILER" $compare_flags "$test_file" "$test_output_dir/${compare_name}.s" > "$test_output_dir/compare.log" 2>&1 \
+        || ! as -o "$test_output_dir/${compare_name}.o" "$test_output_dir/${compare_name}.s" >> "$test_output_dir/compare.log" 2>&1 \
+        || ! gcc -o "$test_output_dir/${compare_name}" "$test_output_dir/${compare_name}.o" "$RUNTIME_DIR/runtime.o" -no-pie -Wl,--allow-multiple-definition -lm >> "$test_output_dir/compare.log" 2>&1; then
+        echo -e "${RED}FAILED${NC} (build with $compare_flags)"
+        echo "FAILED: $test_name (build with $compare_flags)" >> "$RESULTS_FILE"
+        cat "$test_output_dir/compare.log" >> "$RESULTS_FILE"
         echo "" >> "$RESULTS_FILE"
         FAILED=$((FAILED + 1))
         return 1
     fi
-
-    # Step 2: Assemble .s to .o
-    if ! as -o "$test_output_dir/${test_name}.o" "$test_output_dir/${test_name}.s" > "$test_output_dir/assemble.log" 2>&1; then
-        echo -e "${RED}FAILED${NC} (assembly)"
-        echo "FAILED: $test_name (assembly error)" >> "$RESULTS_FILE"
-        cat "$test_output_dir/assemble.log" >> "$RESULTS_FILE"
+    if ! "$test_output_dir/${test_name}" > "$test_output_dir/output.txt" 2>&1 \
+        || ! "$test_output_dir/${compare_name}" > "$test_output_dir/compare.txt" 2>&1 \
+        || ! cmp -s "$test_output_dir/output.txt" "$test_output_dir/compare.txt"; then
+        echo -e "${RED}FAILED${NC} (output differs with $compare_flags)"
+        echo "FAILED: $test_name (output differs with $compare_flags)" >> "$RESULTS_FILE"
+        diff "$test_output_dir/output.txt" "$test_output_dir/compare.txt" >> "$RESULTS_FILE"
         echo "" >> "$RESULTS_FILE"
         FAILED=$((FAILED + 1))
         return 1
     fi
+    echo -e "${GREEN}PASSED${NC}"
+    echo "PASSED: $test_name" >> "$RESULTS_FILE"
+    echo "Output:" >> "$RESULTS_FILE"
+    cat "$test_output_dir/output.txt" >> "$RESULTS_FILE"
+    echo "" >> "$RESULTS_FILE"
+    PASSED=$((PASSED + 1))
+    return 0
+}
 
-    # Step 3: Link with the pure-Runa runtime.o (built from v0.0.8.5/runtime/ + primitives).
-    # No C source involved — the runtime is now 100% Runa.
-    if ! gcc -o "$test_output_dir/${test_name}" "$test_output_dir/${test_name}.o" "$RUNTIME_DIR/runtime.o" -no-pie -Wl,--allow-multiple-definition -lm > "$test_output_dir/link.log" 2>&1; then
-        echo -e "${RED}FAILED${NC} (linking)"
-        echo "FAILED: $test_name (linking error)" >> "$RESULTS_FILE"
-        cat "$test_output_dir/link.log" >> "$RESULTS_FILE"
-        echo "" >> "$RESULTS_FILE"
+# Function to run a test that must fail to compile with the expected errors
+run_error_test() {
+    local test_file="$1"
+    local test_name=$(basename "$test_file" .runa)
+    local test_output_dir="$OUTPUT_DIR/$test_name"
+
+    if "$COMPILER" "$test_file" "$test_output_dir/${test_name}.s" > "$test_output_dir/compile.log" 2>&1; then
+        echo -e "${RED}FAILED${NC} (compiled, expected an error)"
+        echo "FAILED: $test_name (compiled, expected an error)" >> "$RESULTS_FILE"
         FAILED=$((FAILED + 1))
         return 1
     fi
+    while IFS= read -r expected; do
+        if ! grep -qF -- "$expected" "$test_output_dir/compile.log"; then
+            echo -e "${RED}FAILED${NC} (missing expected error)"
+            echo "FAILED: $test_name (missing: $expected)" >> "$RESULTS_FILE"
+            cat "$test_output_dir/compile.log" >> "$RESULTS_FILE"
+            echo "" >> "$RESULTS_FILE"
+            FAILED=$((FAILED + 1))
+            return 1
+        fi
+    done < <(sed -n 's/^Note: expect: //p' "$test_file")
+    echo -e "${GREEN}PASSED${NC}"
+    echo "PASSED: $test_name" >> "$RESULTS_FILE"
+    echo "Output:" >> "$RESULTS_FILE"
+    cat "$test_output_dir/compile.log" >> "$RESULTS_FILE"
+    echo "" >> "$RESULTS_FILE"
+    PASSED=$((PASSED + 1))
+    return 0
+}
+
+# Function to run a single test
+run_test() {
+    local test_file="$1"
+    local test_name=$(basename "$test_file" .runa)
+    local test_output_dir="$OUTPUT_DIR/$test_name"
+    local flags=$(sed -n 's/^Note: flags: //p' "$test_file")
+
+    TOTAL=$((TOTAL + 1))
+
+    # Create test-specific output directory
+    mkdir -p "$test_output_dir"
+
+    echo -n "Testing $test_name... "
+
+    if is_error_test "$(basename "$test_file")"; then
+        run_error_test "$test_file"
+        return $?
+    fi
+
+    if is_exe_test "$(basename "$test_file")"; then
+        # Steps 1-3 in one: --emit=exe compiles, assembles and links
+        if ! RUNA_RUNTIME="$RUNTIME_DIR/runtime.o" "$COMPILER" --emit=exe "$test_file" -o "$test_output_dir/${test_name}" > "$test_output_dir/compile.log" 2>&1; then
+            echo -e "${RED}FAILED${NC} (compilation)"
+            echo "FAILED: $test_name (compilation error)" >> "$RESULTS_FILE"
+            cat "$test_output_dir/compile.log" >> "$RESULTS_FILE"
+            echo "" >> "$RESULTS_FILE"
+            FAILED=$((FAILED + 1))
+            return 1
+        fi
+    elif is_obj_test "$(basename "$test_file")"; then
+        # Steps 1-2 in one: --emit=obj writes the object file directly
+        if ! "$COMPILER" --emit=obj "$test_file" -o "$test_output_dir/${test_name}.o" > "$test_output_dir/compile.log" 2>&1; then
+            echo -e "${RED}FAILED${NC} (compilation)"
+            echo "FAILED: $test_name (compilation error)" >> "$RESULTS_FILE"
+            cat "$test_output_dir/compile.log" >> "$RESULTS_FILE"
+            echo "" >> "$RESULTS_FILE"
+            FAILED=$((FAILED + 1))
+            return 1
+        fi
+    else
+        # Step 1: Compile .runa to .s
+        if ! "$COMPILER" $flags "$test_file" "$test_output_dir/${test_name}.s" > "$test_output_dir/compile.log" 2>&1; then
+            echo -e "${RED}FAILED${NC} (compilation)"
+            echo "FAILED: $test_name (compilation error)" >> "$RESULTS_FILE"
+            cat "$test_output_dir/compile.log" >> "$RESULTS_FILE"
+            echo "" >> "$RESULTS_FILE"
+            FAILED=$((FAILED + 1))
+            return 1
+        fi
+
+        # Step 2: Assemble .s to .o
+        if ! as -o "$test_output_dir/${test_name}.o" "$test_output_dir/${test_name}.s" > "$test_output_dir/assemble.log" 2>&1; then
+            echo -e "${RED}FAILED${NC} (assembly)"
+            echo "FAILED: $test_name (assembly error)" >> "$RESULTS_FILE"
+            cat "$test_output_dir/assemble.log" >> "$RESULTS_FILE"
+            echo "" >> "$RESULTS_FILE"
+            FAILED=$((FAILED + 1))
+            return 1
+        fi
+    fi
+
+    if ! is_exe_test "$(basename "$test_file")"; then
+        # Step 3: Link with the pure-Runa runtime.o (built from v0.0.8.5/runtime/ + primitives).
+        # No C source involved — the runtime is now 100% Runa.
+        if ! gcc -o "$test_output_dir/${test_name}" "$test_output_dir/${test_name}.o" "$RUNTIME_DIR/runtime.o" -no-pie -Wl,--allow-multiple-definition -lm > "$test_output_dir/link.log" 2>&1; then
+            echo -e "${RED}FAILED${NC} (linking)"
+            echo "FAILED: $test_name (linking error)" >> "$RESULTS_FILE"
+            cat "$test_output_dir/link.log" >> "$RESULTS_FILE"
+            echo "" >> "$RESULTS_FILE"
+            FAILED=$((FAILED + 1))
+            return 1
+        fi
+    fi
+
+    while IFS= read -r expected; do
+        if ! grep -qF -- "$expected" "$test_output_dir/compile.log"; then
+            echo -e "${RED}FAILED${NC} (missing expected warning)"
+            echo "FAILED: $test_name (missing warning: $expected)" >> "$RESULTS_FILE"
+            cat "$test_output_dir/compile.log" >> "$RESULTS_FILE"
+            echo "" >> "$RESULTS_FILE"
+            FAILED=$((FAILED + 1))
+            return 1
+        fi
+    done < <(sed -n 's/^Note: expect warning: //p' "$test_file")
+    while IFS= read -r expected; do
+        if ! grep -qF -- "$expected" "$test_output_dir/${test_name}.s"; then
+            echo -e "${RED}FAILED${NC} (missing expected assembly)"
+            echo "FAILED: $test_name (missing assembly: $expected)" >> "$RESULTS_FILE"
+            FAILED=$((FAILED + 1))
+            return 1
+        fi
+    done < <(sed -n 's/^Note: expect asm: //p' "$test_file")
 
     # Step 4: Run the test
+    if is_trap_test "$(basename "$test_file")"; then
+        run_trap_test "$test_file"
+        return $?
+    fi
+    local compare_flags=$(sed -n 's/^Note: compare flags: //p' "$test_file")
+    if [[ -n "$compare_flags" ]]; then
+        run_compare_test "$test_file" "$compare_flags"
+        return $?
+    fi
     if "$test_output_dir/${test_name}" > "$test_output_dir/output.txt" 2>&1; then
         local exit_code=$?
         echo -e "${GREEN}PASSED${NC}"